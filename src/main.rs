@@ -1,12 +1,18 @@
 use std::ops::{Index, IndexMut};
+use std::sync::Mutex;
 use std::time::{Duration, SystemTime};
 
 use noise::{NoiseFn, Seedable};
-use sdl2::event::Event;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sdl2::event::{Event, WindowEvent};
 use sdl2::keyboard::Keycode;
 use sdl2::mouse::MouseButton;
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
+use sdl2::render::Canvas;
+use sdl2::video::{FullscreenType, Window};
+use sdl2::{EventPump, Sdl, VideoSubsystem};
 
 const DEFAULT_VIRT_FPS: u32 = 30;
 
@@ -18,27 +24,140 @@ const PERLIN_SCALE: f64 = 0.16;
 const BILLOW_SCALE: f64 = 0.08;
 const WORLEY_SCALE: f64 = 0.16;
 
+const TOOLBAR_MARGIN: i32 = 8;
+const TOOLBAR_BUTTON_SIZE: u32 = 32;
+const TOOLBAR_BUTTON_GAP: i32 = 8;
+
+const SEED_INDICATOR_BIT_SIZE: u32 = 6;
+const SEED_INDICATOR_BIT_GAP: i32 = 2;
+
+// Stepping uses at most one thread per this many rows, so boards with fewer
+// than twice this many rows step on a single thread.
+const MIN_ROWS_PER_THREAD: usize = 8;
+
+struct DoubleBuffer<T> {
+    a1: Vec<T>,
+    a2: Vec<T>,
+    switch: bool,
+}
+
+impl<T> DoubleBuffer<T> {
+    fn new(a1: Vec<T>, a2: Vec<T>) -> Self {
+        Self {
+            a1,
+            a2,
+            switch: false,
+        }
+    }
+
+    fn front(&self) -> &[T] {
+        if self.switch {
+            &self.a2
+        } else {
+            &self.a1
+        }
+    }
+
+    fn front_mut(&mut self) -> &mut [T] {
+        if self.switch {
+            &mut self.a2
+        } else {
+            &mut self.a1
+        }
+    }
+
+    fn split(&mut self) -> (&[T], &mut [T]) {
+        if self.switch {
+            (&self.a2, &mut self.a1)
+        } else {
+            (&self.a1, &mut self.a2)
+        }
+    }
+
+    fn flip(&mut self) {
+        self.switch = !self.switch;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rule {
+    birth: [bool; 9],
+    survive: [bool; 9],
+}
+
+impl Rule {
+    const PRESETS: &'static [(&'static str, &'static str)] = &[
+        ("Conway's Life", "B3/S23"),
+        ("HighLife", "B36/S23"),
+        ("Day & Night", "B3678/S34678"),
+        ("Seeds", "B2/S"),
+    ];
+
+    fn conway() -> Self {
+        Self::parse("B3/S23").unwrap()
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        let (b_part, s_part) = s.trim().split_once('/')?;
+        let b_digits = b_part.strip_prefix(['B', 'b'])?;
+        let s_digits = s_part.strip_prefix(['S', 's'])?;
+
+        let mut birth = [false; 9];
+        for c in b_digits.chars() {
+            let n = c.to_digit(10)?;
+            if n > 8 {
+                return None;
+            }
+            birth[n as usize] = true;
+        }
+
+        let mut survive = [false; 9];
+        for c in s_digits.chars() {
+            let n = c.to_digit(10)?;
+            if n > 8 {
+                return None;
+            }
+            survive[n as usize] = true;
+        }
+
+        Some(Self { birth, survive })
+    }
+
+    fn next_preset(self) -> Self {
+        let presets: Vec<Self> = Self::PRESETS
+            .iter()
+            .map(|(_, rule)| Self::parse(rule).unwrap())
+            .collect();
+
+        let idx = presets.iter().position(|&r| r == self).unwrap_or(0);
+        presets[(idx + 1) % presets.len()]
+    }
+}
+
 struct Board<const W: usize, const H: usize> {
-    fields: Vec<bool>,
+    buf: DoubleBuffer<bool>,
 }
 
 impl<const W: usize, const H: usize> Index<usize> for Board<W, H> {
     type Output = [bool];
 
     fn index(&self, index: usize) -> &Self::Output {
-        &self.fields[index * W..(index + 1) * W]
+        &self.buf.front()[index * W..(index + 1) * W]
     }
 }
 
 impl<const W: usize, const H: usize> IndexMut<usize> for Board<W, H> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.fields[index * W..(index + 1) * W]
+        &mut self.buf.front_mut()[index * W..(index + 1) * W]
     }
 }
 
 impl<const W: usize, const H: usize> Board<W, H> {
     fn new(fields: Vec<bool>) -> Self {
-        Self { fields }
+        let back = vec![false; H * W];
+        Self {
+            buf: DoubleBuffer::new(fields, back),
+        }
     }
 
     fn clear() -> Self {
@@ -60,12 +179,14 @@ impl<const W: usize, const H: usize> Board<W, H> {
         Self::new(fields)
     }
 
-    fn random() -> Self {
-        Self::generate(|_, _| rand::random())
+    fn random(seed: u32) -> Self {
+        let rng = Mutex::new(StdRng::seed_from_u64(seed as u64));
+
+        Self::generate(move |_, _| rng.lock().unwrap().gen())
     }
 
-    fn perlin() -> Self {
-        let noise = noise::Perlin::new().set_seed(rand::random());
+    fn perlin(seed: u32) -> Self {
+        let noise = noise::Perlin::new().set_seed(seed);
 
         Self::generate(|x, y| {
             let val = noise.get([x as f64 * PERLIN_SCALE, y as f64 * PERLIN_SCALE]);
@@ -74,8 +195,8 @@ impl<const W: usize, const H: usize> Board<W, H> {
         })
     }
 
-    fn billow() -> Self {
-        let noise = noise::Billow::new().set_seed(rand::random());
+    fn billow(seed: u32) -> Self {
+        let noise = noise::Billow::new().set_seed(seed);
 
         Self::generate(|x, y| {
             let val = noise.get([x as f64 * BILLOW_SCALE, y as f64 * BILLOW_SCALE]);
@@ -84,8 +205,8 @@ impl<const W: usize, const H: usize> Board<W, H> {
         })
     }
 
-    fn worley() -> Self {
-        let noise = noise::Worley::new().set_seed(rand::random());
+    fn worley(seed: u32) -> Self {
+        let noise = noise::Worley::new().set_seed(seed);
 
         Self::generate(|x, y| {
             let val = noise.get([x as f64 * WORLEY_SCALE, y as f64 * WORLEY_SCALE]);
@@ -94,6 +215,62 @@ impl<const W: usize, const H: usize> Board<W, H> {
         })
     }
 
+    fn cave(seed: u32) -> Self {
+        const FILL_PROBABILITY: f64 = 0.45;
+        const SMOOTHING_PASSES: usize = 5;
+
+        let mut rng = StdRng::seed_from_u64(seed as u64);
+        let mut fields: Vec<bool> = (0..H * W)
+            .map(|n| {
+                let x = n % W;
+                let y = n / W;
+                let border = x == 0 || y == 0 || x == W - 1 || y == H - 1;
+
+                border || rng.gen::<f64>() < FILL_PROBABILITY
+            })
+            .collect();
+
+        for _ in 0..SMOOTHING_PASSES {
+            fields = Self::smooth_cave(&fields);
+        }
+
+        Self::new(fields)
+    }
+
+    fn smooth_cave(fields: &[bool]) -> Vec<bool> {
+        (0..H * W)
+            .map(|n| {
+                let x = n % W;
+                let y = n / W;
+                match Self::cave_neighbours(fields, x, y) {
+                    count if count >= 5 => true,
+                    count if count <= 3 => false,
+                    _ => fields[n],
+                }
+            })
+            .collect()
+    }
+
+    fn cave_neighbours(fields: &[bool], x: usize, y: usize) -> usize {
+        let mut alive = 0;
+        for _y in -1isize..=1 {
+            for _x in -1isize..=1 {
+                if (_x, _y) == (0, 0) {
+                    continue;
+                }
+
+                let nx = x as isize + _x;
+                let ny = y as isize + _y;
+                let out_of_bounds = nx < 0 || ny < 0 || nx >= W as isize || ny >= H as isize;
+                if out_of_bounds || fields[ny as usize * W + nx as usize] {
+                    alive += 1;
+                }
+            }
+        }
+
+        alive
+    }
+
     fn glider() -> Self {
         let mut new = Self::clear();
 
@@ -130,20 +307,47 @@ impl<const W: usize, const H: usize> Board<W, H> {
         (0..H).into_iter().map(move |y| &self[y])
     }
 
-    fn next(&self) -> Self {
-        Self::generate(|x, y| {
-            let val = self[y][x];
-            let neighbours = self.neighbours(x, y);
+    fn step(&mut self, rule: &Rule) {
+        let (front, back) = self.buf.split();
+
+        let thread_count = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(H / MIN_ROWS_PER_THREAD)
+            .max(1);
+
+        if thread_count <= 1 {
+            Self::step_rows(front, back, rule, 0);
+        } else {
+            let rows_per_chunk = H.div_ceil(thread_count);
+            std::thread::scope(|scope| {
+                for (i, chunk) in back.chunks_mut(rows_per_chunk * W).enumerate() {
+                    let y_offset = i * rows_per_chunk;
+                    scope.spawn(move || Self::step_rows(front, chunk, rule, y_offset));
+                }
+            });
+        }
 
-            match (val, neighbours) {
-                (true, 2) => true,
-                (_, 3) => true,
-                (_, _) => false,
-            }
-        })
+        self.buf.flip();
+    }
+
+    fn step_rows(front: &[bool], chunk: &mut [bool], rule: &Rule, y_offset: usize) {
+        for (i, alive) in chunk.iter_mut().enumerate() {
+            let y = y_offset + i / W;
+            let x = i % W;
+
+            let val = front[y * W + x];
+            let neighbours = Self::neighbours(front, x, y);
+
+            *alive = if val {
+                rule.survive[neighbours]
+            } else {
+                rule.birth[neighbours]
+            };
+        }
     }
 
-    fn neighbours(&self, x: usize, y: usize) -> usize {
+    fn neighbours(front: &[bool], x: usize, y: usize) -> usize {
         let x_low = if x == 0 { 0 } else { x - 1 };
         let x_high = if x == W - 1 { W - 1 } else { x + 1 };
         let y_low = if y == 0 { 0 } else { y - 1 };
@@ -155,7 +359,7 @@ impl<const W: usize, const H: usize> Board<W, H> {
                 if (_x, _y) == (x, y) {
                     continue;
                 }
-                if self[_y][_x] {
+                if front[_y * W + _x] {
                     neighbours += 1;
                 }
             }
@@ -188,125 +392,478 @@ impl<const W: usize, const H: usize> Board<W, H> {
     }
 }
 
-fn main() {
-    let sdl_context = sdl2::init().unwrap();
-    let video_subsystem = sdl_context.video().unwrap();
+#[derive(Debug, Clone, Copy)]
+enum Generator {
+    Clear,
+    Random,
+    Perlin,
+    Billow,
+    Worley,
+    Cave,
+    Glider,
+    GliderGun,
+}
+
+impl Generator {
+    fn build<const W: usize, const H: usize>(self, seed: u32) -> Board<W, H> {
+        match self {
+            Generator::Clear => Board::clear(),
+            Generator::Random => Board::random(seed),
+            Generator::Perlin => Board::perlin(seed),
+            Generator::Billow => Board::billow(seed),
+            Generator::Worley => Board::worley(seed),
+            Generator::Cave => Board::cave(seed),
+            Generator::Glider => Board::glider(),
+            Generator::GliderGun => Board::glider_gun(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ToolbarAction {
+    PlayPause,
+    Step,
+    SpeedUp,
+    Restart,
+}
+
+struct ToolbarButton {
+    rect: Rect,
+    action: ToolbarAction,
+}
 
-    let window = video_subsystem
-        .window("fun", 1600, 900)
-        .position_centered()
-        .build()
-        .unwrap();
+struct Toolbar {
+    buttons: Vec<ToolbarButton>,
+}
+
+impl Toolbar {
+    fn new() -> Self {
+        let actions = [
+            ToolbarAction::PlayPause,
+            ToolbarAction::Step,
+            ToolbarAction::SpeedUp,
+            ToolbarAction::Restart,
+        ];
 
-    let mut canvas = window.into_canvas().build().unwrap();
+        let buttons = actions
+            .into_iter()
+            .enumerate()
+            .map(|(i, action)| {
+                let x = TOOLBAR_MARGIN + i as i32 * (TOOLBAR_BUTTON_SIZE as i32 + TOOLBAR_BUTTON_GAP);
+                let rect = Rect::new(x, TOOLBAR_MARGIN, TOOLBAR_BUTTON_SIZE, TOOLBAR_BUTTON_SIZE);
+                ToolbarButton { rect, action }
+            })
+            .collect();
 
-    canvas.set_draw_color(Color::RGB(20, 20, 20));
-    canvas.clear();
-    canvas.present();
-    let mut event_pump = sdl_context.event_pump().unwrap();
+        Self { buttons }
+    }
 
-    let mut update_rate = DEFAULT_VIRT_FPS;
-    let mut last_update = std::time::SystemTime::now();
-    let mut last_render = std::time::SystemTime::now();
-    let mut pause = false;
-    let mut last_x = 0;
-    let mut last_y = 0;
+    fn hit_test(&self, x: i32, y: i32) -> Option<ToolbarAction> {
+        self.buttons
+            .iter()
+            .find(|button| button.rect.contains_point((x, y)))
+            .map(|button| button.action)
+    }
 
-    let mut board = Board::<WIDTH, HEIGHT>::random();
+    fn draw(&self, canvas: &mut Canvas<Window>, pause: bool, update_rate: u32) {
+        for button in &self.buttons {
+            let color = match button.action {
+                ToolbarAction::PlayPause if pause => Color::RGB(160, 80, 80),
+                ToolbarAction::PlayPause => Color::RGB(80, 160, 80),
+                _ => Color::RGB(70, 70, 70),
+            };
+            canvas.set_draw_color(color);
+            canvas.fill_rect(button.rect).ok();
+        }
 
-    'running: loop {
+        // Speed indicator: a bar to the right of the buttons whose length
+        // grows with `update_rate`.
+        let last_button = self.buttons.last().unwrap().rect;
+        let indicator_x = last_button.x() + last_button.width() as i32 + TOOLBAR_BUTTON_GAP;
+        let indicator_width = update_rate.min(128);
+        canvas.set_draw_color(Color::RGB(90, 90, 160));
+        canvas
+            .fill_rect(Rect::new(
+                indicator_x,
+                TOOLBAR_MARGIN,
+                indicator_width,
+                TOOLBAR_BUTTON_SIZE,
+            ))
+            .ok();
+    }
+}
+
+struct AppBuilder {
+    title: String,
+    width: u32,
+    height: u32,
+    virt_fps: u32,
+}
+
+impl AppBuilder {
+    fn new() -> Self {
+        Self {
+            title: "fun".to_string(),
+            width: 1600,
+            height: 900,
+            virt_fps: DEFAULT_VIRT_FPS,
+        }
+    }
+
+    fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    fn with_resolution(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    fn with_virt_fps(mut self, virt_fps: u32) -> Self {
+        self.virt_fps = virt_fps;
+        self
+    }
+
+    fn build(self) -> App {
+        let sdl_context = sdl2::init().unwrap();
+        let video_subsystem = sdl_context.video().unwrap();
+
+        let mut window = video_subsystem
+            .window(&self.title, self.width, self.height)
+            .position_centered()
+            .resizable()
+            .build()
+            .unwrap();
+        window
+            .set_minimum_size(WIDTH as u32, HEIGHT as u32)
+            .unwrap();
+
+        let mut canvas = window.into_canvas().build().unwrap();
+        canvas.set_draw_color(Color::RGB(20, 20, 20));
+        canvas.clear();
+        canvas.present();
+
+        let event_pump = sdl_context.event_pump().unwrap();
         let (width, height) = canvas.output_size().unwrap();
-        let tile_width = width / WIDTH as u32;
-        let tile_height = height / HEIGHT as u32;
-        let current_time = SystemTime::now();
-
-        for e in event_pump.poll_iter() {
-            match e {
-                Event::Quit { .. } => break 'running,
-                Event::KeyDown {
-                    keycode: Some(code),
-                    ..
-                } => match code {
-                    Keycode::Q => break 'running,
-                    Keycode::C => board = Board::clear(),
-                    Keycode::R => board = Board::random(),
-                    Keycode::P => board = Board::perlin(),
-                    Keycode::B => board = Board::billow(),
-                    Keycode::W => board = Board::worley(),
-                    Keycode::L => board = Board::glider(),
-                    Keycode::G => board = Board::glider_gun(),
-                    Keycode::Space => pause = !pause,
-                    Keycode::Num0 => update_rate = DEFAULT_VIRT_FPS,
-                    Keycode::Equals => update_rate += 1,
-                    Keycode::Minus => {
-                        if update_rate > 1 {
-                            update_rate -= 1
+
+        let current_generator = Generator::Random;
+        let seed = rand::random();
+
+        App {
+            _sdl_context: sdl_context,
+            video_subsystem,
+            canvas,
+            event_pump,
+            tile_width: (width / WIDTH as u32).max(1),
+            tile_height: (height / HEIGHT as u32).max(1),
+            fullscreen: false,
+            update_rate: self.virt_fps,
+            last_update: SystemTime::now(),
+            last_render: SystemTime::now(),
+            pause: false,
+            last_x: 0,
+            last_y: 0,
+            drawing: false,
+            rule: Rule::conway(),
+            rule_input: None,
+            toolbar: Toolbar::new(),
+            current_generator,
+            seed,
+            board: current_generator.build(seed),
+        }
+    }
+}
+
+struct App {
+    _sdl_context: Sdl,
+    video_subsystem: VideoSubsystem,
+    canvas: Canvas<Window>,
+    event_pump: EventPump,
+
+    tile_width: u32,
+    tile_height: u32,
+    fullscreen: bool,
+
+    update_rate: u32,
+    last_update: SystemTime,
+    last_render: SystemTime,
+    pause: bool,
+    last_x: usize,
+    last_y: usize,
+    drawing: bool,
+
+    rule: Rule,
+    rule_input: Option<String>,
+    toolbar: Toolbar,
+    current_generator: Generator,
+    seed: u32,
+    board: Board<WIDTH, HEIGHT>,
+}
+
+impl App {
+    fn recompute_tile_size(&mut self) {
+        let (width, height) = self.canvas.output_size().unwrap();
+        self.tile_width = (width / WIDTH as u32).max(1);
+        self.tile_height = (height / HEIGHT as u32).max(1);
+    }
+
+    fn toggle_fullscreen(&mut self) {
+        self.fullscreen = !self.fullscreen;
+        let mode = if self.fullscreen {
+            FullscreenType::Desktop
+        } else {
+            FullscreenType::Off
+        };
+        self.canvas.window_mut().set_fullscreen(mode).ok();
+        self.recompute_tile_size();
+    }
+
+    fn draw_seed_indicator(&mut self) {
+        let (_, height) = self.canvas.output_size().unwrap();
+        let y = height as i32 - TOOLBAR_MARGIN - SEED_INDICATOR_BIT_SIZE as i32;
+
+        for bit in 0..u32::BITS {
+            let lit = (self.seed >> bit) & 1 == 1;
+            let color = if lit {
+                Color::RGB(200, 170, 60)
+            } else {
+                Color::RGB(50, 50, 50)
+            };
+            self.canvas.set_draw_color(color);
+
+            let x = TOOLBAR_MARGIN
+                + bit as i32 * (SEED_INDICATOR_BIT_SIZE as i32 + SEED_INDICATOR_BIT_GAP);
+            self.canvas
+                .fill_rect(Rect::new(
+                    x,
+                    y,
+                    SEED_INDICATOR_BIT_SIZE,
+                    SEED_INDICATOR_BIT_SIZE,
+                ))
+                .ok();
+        }
+    }
+
+    // Rule indicator: birth and survival bits as two rows, so the active
+    // rule stays visible while typing a new one with `/`.
+    fn draw_rule_indicator(&mut self) {
+        let (_, height) = self.canvas.output_size().unwrap();
+        let survive_y = height as i32
+            - TOOLBAR_MARGIN
+            - SEED_INDICATOR_BIT_SIZE as i32
+            - (SEED_INDICATOR_BIT_SIZE as i32 + SEED_INDICATOR_BIT_GAP);
+        let birth_y = survive_y - (SEED_INDICATOR_BIT_SIZE as i32 + SEED_INDICATOR_BIT_GAP);
+
+        let editing = self.rule_input.is_some();
+        for (row_y, bits) in [(birth_y, self.rule.birth), (survive_y, self.rule.survive)] {
+            for (n, &lit) in bits.iter().enumerate() {
+                let color = match (lit, editing) {
+                    (true, false) => Color::RGB(80, 160, 200),
+                    (true, true) => Color::RGB(160, 120, 200),
+                    (false, _) => Color::RGB(50, 50, 50),
+                };
+                self.canvas.set_draw_color(color);
+
+                let x = TOOLBAR_MARGIN
+                    + n as i32 * (SEED_INDICATOR_BIT_SIZE as i32 + SEED_INDICATOR_BIT_GAP);
+                self.canvas
+                    .fill_rect(Rect::new(
+                        x,
+                        row_y,
+                        SEED_INDICATOR_BIT_SIZE,
+                        SEED_INDICATOR_BIT_SIZE,
+                    ))
+                    .ok();
+            }
+        }
+    }
+
+    fn run(&mut self) {
+        'running: loop {
+            let current_time = SystemTime::now();
+
+            while let Some(e) = self.event_pump.poll_event() {
+                match e {
+                    Event::Quit { .. } => break 'running,
+                    Event::Window {
+                        win_event: WindowEvent::Resized(..) | WindowEvent::SizeChanged(..),
+                        ..
+                    } => self.recompute_tile_size(),
+                    Event::KeyDown {
+                        keycode: Some(code),
+                        ..
+                    } if self.rule_input.is_some() => match code {
+                        Keycode::Return => {
+                            if let Some(parsed) = Rule::parse(self.rule_input.as_deref().unwrap())
+                            {
+                                self.rule = parsed;
+                            }
+                            self.rule_input = None;
+                            self.video_subsystem.text_input().stop();
+                        }
+                        Keycode::Escape => {
+                            self.rule_input = None;
+                            self.video_subsystem.text_input().stop();
+                        }
+                        Keycode::Backspace => {
+                            self.rule_input.as_mut().unwrap().pop();
+                        }
+                        _ => (),
+                    },
+                    Event::KeyDown {
+                        keycode: Some(code),
+                        ..
+                    } => match code {
+                        Keycode::Q => break 'running,
+                        Keycode::C => self.regenerate(Generator::Clear),
+                        Keycode::R => self.regenerate(Generator::Random),
+                        Keycode::P => self.regenerate(Generator::Perlin),
+                        Keycode::B => self.regenerate(Generator::Billow),
+                        Keycode::W => self.regenerate(Generator::Worley),
+                        Keycode::A => self.regenerate(Generator::Cave),
+                        Keycode::L => self.regenerate(Generator::Glider),
+                        Keycode::G => self.regenerate(Generator::GliderGun),
+                        Keycode::Space => self.pause = !self.pause,
+                        Keycode::Num0 => self.update_rate = DEFAULT_VIRT_FPS,
+                        Keycode::Equals => self.update_rate += 1,
+                        Keycode::Minus => {
+                            if self.update_rate > 1 {
+                                self.update_rate -= 1
+                            }
+                        }
+                        Keycode::Tab => self.rule = self.rule.next_preset(),
+                        Keycode::Slash => {
+                            self.rule_input = Some(String::new());
+                            self.video_subsystem.text_input().start();
+                        }
+                        Keycode::F11 => self.toggle_fullscreen(),
+                        Keycode::N => {
+                            self.seed = rand::random();
+                            self.regenerate(self.current_generator);
+                        }
+                        Keycode::M => self.regenerate(self.current_generator),
+                        Keycode::LeftBracket => {
+                            self.seed = self.seed.wrapping_sub(1);
+                            self.regenerate(self.current_generator);
+                        }
+                        Keycode::RightBracket => {
+                            self.seed = self.seed.wrapping_add(1);
+                            self.regenerate(self.current_generator);
+                        }
+                        _ => (),
+                    },
+                    Event::TextInput { text, .. } => {
+                        if let Some(input) = self.rule_input.as_mut() {
+                            input.push_str(&text);
                         }
                     }
-                    _ => (),
-                },
-                Event::MouseButtonDown {
-                    x, y, mouse_btn, ..
-                } => {
-                    last_x = x as usize / tile_width as usize;
-                    last_y = y as usize / tile_height as usize;
-                    board[last_y][last_x] = matches!(mouse_btn, MouseButton::Left);
-                }
-                Event::MouseMotion {
-                    x, y, mousestate, ..
-                } => {
-                    let vx = x as usize / tile_width as usize;
-                    let vy = y as usize / tile_height as usize;
-
-                    if mousestate.left() {
-                        board.line(last_x, last_y, vx, vy, true);
-                    } else if mousestate.right() {
-                        board.line(last_x, last_y, vx, vy, false);
+                    Event::MouseButtonDown {
+                        x, y, mouse_btn, ..
+                    } => {
+                        if let Some(action) = self.toolbar.hit_test(x, y) {
+                            self.drawing = false;
+                            match action {
+                                ToolbarAction::PlayPause => self.pause = !self.pause,
+                                ToolbarAction::Step => self.board.step(&self.rule),
+                                ToolbarAction::SpeedUp => self.update_rate += 1,
+                                ToolbarAction::Restart => {
+                                    self.regenerate(self.current_generator)
+                                }
+                            }
+                        } else {
+                            self.drawing = true;
+                            self.last_x = x as usize / self.tile_width as usize;
+                            self.last_y = y as usize / self.tile_height as usize;
+                            self.board[self.last_y][self.last_x] =
+                                matches!(mouse_btn, MouseButton::Left);
+                        }
                     }
+                    Event::MouseMotion {
+                        x, y, mousestate, ..
+                    } => {
+                        let vx = x as usize / self.tile_width as usize;
+                        let vy = y as usize / self.tile_height as usize;
+
+                        if self.drawing {
+                            if mousestate.left() {
+                                self.board.line(self.last_x, self.last_y, vx, vy, true);
+                            } else if mousestate.right() {
+                                self.board.line(self.last_x, self.last_y, vx, vy, false);
+                            }
+                        }
 
-                    last_x = vx;
-                    last_y = vy;
+                        self.last_x = vx;
+                        self.last_y = vy;
+                    }
+                    _ => (),
                 }
-                _ => (),
             }
-        }
 
-        if !pause {
-            let measured_nanos = current_time.duration_since(last_update).unwrap().as_nanos();
-            let nanos = 1_000_000_000 / update_rate as u128;
-            if measured_nanos > nanos {
-                board = board.next();
-                last_update = current_time;
+            if !self.pause {
+                let measured_nanos = current_time
+                    .duration_since(self.last_update)
+                    .unwrap()
+                    .as_nanos();
+                let nanos = 1_000_000_000 / self.update_rate as u128;
+                if measured_nanos > nanos {
+                    self.board.step(&self.rule);
+                    self.last_update = current_time;
+                }
             }
-        }
 
-        let measured_nanos = current_time.duration_since(last_render).unwrap().as_nanos();
-        let nanos = 1_000_000_000 / FPS as u128;
-        if measured_nanos > nanos {
-            canvas.set_draw_color(Color::RGB(20, 20, 20));
-            canvas.clear();
-
-            for (y, row) in board.rows().enumerate() {
-                for (x, &field) in row.iter().enumerate() {
-                    if field {
-                        canvas.set_draw_color(Color::RGB(200, 200, 200));
-                    } else {
-                        canvas.set_draw_color(Color::RGB(20, 20, 20));
+            let measured_nanos = current_time
+                .duration_since(self.last_render)
+                .unwrap()
+                .as_nanos();
+            let nanos = 1_000_000_000 / FPS as u128;
+            if measured_nanos > nanos {
+                self.canvas.set_draw_color(Color::RGB(20, 20, 20));
+                self.canvas.clear();
+
+                for (y, row) in self.board.rows().enumerate() {
+                    for (x, &field) in row.iter().enumerate() {
+                        if field {
+                            self.canvas.set_draw_color(Color::RGB(200, 200, 200));
+                        } else {
+                            self.canvas.set_draw_color(Color::RGB(20, 20, 20));
+                        }
+                        self.canvas
+                            .fill_rect(Rect::new(
+                                self.tile_width as i32 * x as i32,
+                                self.tile_height as i32 * y as i32,
+                                self.tile_width,
+                                self.tile_height,
+                            ))
+                            .ok();
                     }
-                    canvas
-                        .fill_rect(Rect::new(
-                            tile_width as i32 * x as i32,
-                            tile_height as i32 * y as i32,
-                            tile_width,
-                            tile_height,
-                        ))
-                        .ok();
                 }
+
+                self.toolbar.draw(&mut self.canvas, self.pause, self.update_rate);
+                self.draw_seed_indicator();
+                self.draw_rule_indicator();
+
+                self.canvas.present();
+                self.last_render = current_time;
             }
-            canvas.present();
-            last_render = current_time;
+
+            std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 1000));
         }
+    }
 
-        std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 1000));
+    fn regenerate(&mut self, generator: Generator) {
+        self.current_generator = generator;
+        self.board = generator.build(self.seed);
     }
 }
+
+fn main() {
+    let mut app = AppBuilder::new()
+        .with_title("fun")
+        .with_resolution(1600, 900)
+        .with_virt_fps(DEFAULT_VIRT_FPS)
+        .build();
+
+    app.run();
+}